@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use parcom::json::{json_value, JsonValue};
+use parcom::parser::Parser;
+
+/// Build a moderately large JSON document (an array of 2,000 small
+/// objects, each holding a nested array) so that
+/// regressions in the combinator hot paths (`zero_or_more`, `pair`,
+/// `either`) show up in the benchmark rather than only in a profiler.
+fn large_document() -> JsonValue {
+    let row = JsonValue::Object(vec![
+        ("id".to_owned(), JsonValue::Number(1.0)),
+        ("name".to_owned(), JsonValue::String("widget".to_owned())),
+        ("active".to_owned(), JsonValue::Bool(true)),
+        (
+            "tags".to_owned(),
+            JsonValue::Array(vec![
+                JsonValue::String("a".to_owned()),
+                JsonValue::String("b".to_owned()),
+                JsonValue::String("c".to_owned()),
+            ]),
+        ),
+    ]);
+
+    JsonValue::Array((0..2_000).map(|_| row.clone()).collect())
+}
+
+fn bench_json_value(c: &mut Criterion) {
+    let text = large_document().to_string();
+
+    c.bench_function("json_value parses a large document", |b| {
+        b.iter(|| json_value().parse(black_box(&text)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_json_value);
+criterion_main!(benches);
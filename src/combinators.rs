@@ -1,14 +1,79 @@
-use crate::parser::Parser;
+use crate::parser::{offset_between, ParseError, Parser};
+use crate::representation::Representation;
+use std::marker::PhantomData;
 
-pub fn map<'a, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B>
+pub struct Map<P, F, A> {
+    parser: P,
+    map_fn: F,
+    _input: PhantomData<A>,
+}
+
+impl<'a, P, F, A, B> Parser<'a, B> for Map<P, F, A>
 where
     P: Parser<'a, A>,
     F: Fn(A) -> B,
 {
-    move |input| {
-        parser
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, B> {
+        self.parser
             .parse(input)
-            .map(|(result, next_input)| (map_fn(result), next_input))
+            .map(|(result, next_input)| ((self.map_fn)(result), next_input))
+    }
+
+    // `map` only transforms the output value, not the shape of what's
+    // recognized, so it just forwards the wrapped parser's grammar as-is.
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+pub fn map<'a, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B>
+where
+    P: Parser<'a, A>,
+    F: Fn(A) -> B,
+{
+    Map {
+        parser,
+        map_fn,
+        _input: PhantomData,
+    }
+}
+
+pub struct AndThen<P, F, A, NextP> {
+    parser: P,
+    f: F,
+    _input: PhantomData<A>,
+    _next: PhantomData<NextP>,
+}
+
+impl<'a, P, F, A, B, NextP> Parser<'a, B> for AndThen<P, F, A, NextP>
+where
+    P: Parser<'a, A>,
+    NextP: Parser<'a, B>,
+    F: Fn(A) -> NextP,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, B> {
+        let (result, next_input) = self.parser.parse(input)?;
+        (self.f)(result)
+            .parse(next_input)
+            .map_err(|err| err.shift(offset_between(input, next_input)))
+    }
+
+    // `f` only produces the second parser once it has a value from the
+    // first, so there's no `NextP` to call `.representation()`/
+    // `.productions()` on statically: the best this can report on its own
+    // is the first parser's shape. A caller that knows the continuation's
+    // shape up front regardless (e.g. it doesn't actually depend on the
+    // value threaded through `f`) can still surface it with `.describe(...)`.
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
     }
 }
 
@@ -18,28 +83,83 @@ where
     NextP: Parser<'a, B>,
     F: Fn(A) -> NextP,
 {
-    // move |input| match parser.parse(input) {
-    //     Ok((result, next_input)) => f(result).parse(next_input),
-    //     Err(err) => Err(err),
-    // }
-    move |input| parser.parse(input).and_then(|(result, next_input)| f(result).parse(next_input))
+    AndThen {
+        parser,
+        f,
+        _input: PhantomData,
+        _next: PhantomData,
+    }
+}
 
+pub struct Pred<P, F> {
+    parser: P,
+    pred_fn: F,
 }
 
+impl<'a, P, A, F> Parser<'a, A> for Pred<P, F>
+where
+    P: Parser<'a, A>,
+    F: Fn(&A) -> bool,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        let (result, next_input) = self.parser.parse(input)?;
+
+        if (self.pred_fn)(&result) {
+            Ok((result, next_input))
+        } else {
+            Err(ParseError::new(input, "value matching predicate"))
+        }
+    }
+
+    // The predicate narrows which values are accepted but doesn't change
+    // the syntax being recognized, so it forwards the inner shape too.
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
 
 pub fn pred<'a, P, A, F>(parser: P, pred_fn: F) -> impl Parser<'a, A>
 where
     P: Parser<'a, A>,
     F: Fn(&A) -> bool,
 {
-    move |input: &'a str| {
-        if let Ok((result, next_input)) = parser.parse(input) {
-            if pred_fn(&result) {
-                return Ok((result, next_input));
-            }
-        }
+    Pred { parser, pred_fn }
+}
+
+pub struct Pair<P1, P2> {
+    parser1: P1,
+    parser2: P2,
+}
+
+impl<'a, P1, P2, R1, R2> Parser<'a, (R1, R2)> for Pair<P1, P2>
+where
+    P1: Parser<'a, R1>,
+    P2: Parser<'a, R2>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, (R1, R2)> {
+        let (result1, next_input) = self.parser1.parse(input)?;
+
+        self.parser2
+            .parse(next_input)
+            .map(|(result2, final_input)| ((result1, result2), final_input))
+            .map_err(|err| err.shift(offset_between(input, next_input)))
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Sequence(vec![
+            self.parser1.representation(),
+            self.parser2.representation(),
+        ])
+    }
 
-        Err(input)
+    fn productions(&self) -> Vec<(String, Representation)> {
+        let mut productions = self.parser1.productions();
+        productions.extend(self.parser2.productions());
+        productions
     }
 }
 
@@ -48,13 +168,7 @@ where
     P1: Parser<'a, R1>,
     P2: Parser<'a, R2>,
 {
-    move |input| {
-        parser1.parse(input).and_then(|(result1, next_input)| {
-            parser2
-                .parse(next_input)
-                .map(|(result2, final_input)| ((result1, result2), final_input))
-        })
-    }
+    Pair { parser1, parser2 }
 }
 
 pub fn left<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R1>
@@ -73,43 +187,147 @@ where
     map(pair(parser1, parser2), |(_left, right)| right)
 }
 
-pub fn one_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+pub struct OneOrMore<P> {
+    parser: P,
+}
+
+impl<'a, P, A> Parser<'a, Vec<A>> for OneOrMore<P>
 where
     P: Parser<'a, A>,
 {
-    move |mut input| {
+    fn parse(&self, mut input: &'a str) -> crate::parser::ParseResult<'a, Vec<A>> {
+        let start = input;
         let mut results = Vec::new();
 
-        if let Ok((first_result, next_input)) = parser.parse(input) {
-            input = next_input;
-            results.push(first_result);
-        } else {
-            return Err(input);
-        }
+        let (first_result, next_input) = self.parser.parse(input)?;
+        input = next_input;
+        results.push(first_result);
 
-        while let Ok((next_result, next_input)) = parser.parse(input) {
-            input = next_input;
-            results.push(next_result);
+        loop {
+            match self.parser.parse(input) {
+                Ok((next_result, next_input)) => {
+                    input = next_input;
+                    results.push(next_result);
+                }
+                // A fatal failure means a later repetition committed partway
+                // (e.g. via `cut`) before failing, so it's a real error, not
+                // "no more repetitions". Shift it by how far earlier
+                // repetitions already advanced past `start`, or it would
+                // report a position from partway through this combinator's
+                // input instead of the original input.
+                Err(err) if err.fatal => return Err(err.shift(offset_between(start, input))),
+                Err(_) => break,
+            }
         }
 
         Ok((results, input))
     }
+
+    fn representation(&self) -> Representation {
+        let item = self.parser.representation();
+        Representation::Sequence(vec![item.clone(), Representation::Repeated(Box::new(item))])
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
 }
 
-pub fn zero_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+pub fn one_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A>,
+{
+    OneOrMore { parser }
+}
+
+pub struct ZeroOrMore<P> {
+    parser: P,
+}
+
+impl<'a, P, A> Parser<'a, Vec<A>> for ZeroOrMore<P>
 where
     P: Parser<'a, A>,
 {
-    move |mut input| {
+    fn parse(&self, mut input: &'a str) -> crate::parser::ParseResult<'a, Vec<A>> {
+        let start = input;
         let mut results = Vec::new();
 
-        while let Ok((next_result, next_input)) = parser.parse(input) {
-            input = next_input;
-            results.push(next_result);
+        loop {
+            match self.parser.parse(input) {
+                Ok((next_result, next_input)) => {
+                    input = next_input;
+                    results.push(next_result);
+                }
+                // A fatal failure means this repetition committed partway
+                // (e.g. via `cut`) before failing, so it's a real error, not
+                // "no more repetitions". Shift it by how far earlier
+                // repetitions already advanced past `start`, or it would
+                // report a position from partway through this combinator's
+                // input instead of the original input.
+                Err(err) if err.fatal => return Err(err.shift(offset_between(start, input))),
+                Err(_) => break,
+            }
         }
 
         Ok((results, input))
     }
+
+    fn representation(&self) -> Representation {
+        Representation::Repeated(Box::new(self.parser.representation()))
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+pub fn zero_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A>,
+{
+    ZeroOrMore { parser }
+}
+
+pub struct Either<P1, P2> {
+    parser1: P1,
+    parser2: P2,
+}
+
+impl<'a, P1, P2, A> Parser<'a, A> for Either<P1, P2>
+where
+    P1: Parser<'a, A>,
+    P2: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        match self.parser1.parse(input) {
+            ok @ Ok(_) => ok,
+            // A fatal failure means the input committed past the point of
+            // ambiguity (see `cut`), so trying `parser2` against the same
+            // input would just re-fail in a more confusing way.
+            Err(err1) if err1.fatal => Err(err1),
+            Err(err1) => match self.parser2.parse(input) {
+                ok @ Ok(_) => ok,
+                // A fatal failure from the second branch is the one real
+                // error to report, not something to dilute by merging it
+                // with the first (abandoned) branch's expectations.
+                Err(err2) if err2.fatal => Err(err2),
+                Err(err2) => Err(merge_errors(err1, err2)),
+            },
+        }
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Choice(vec![
+            self.parser1.representation(),
+            self.parser2.representation(),
+        ])
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        let mut productions = self.parser1.productions();
+        productions.extend(self.parser2.productions());
+        productions
+    }
 }
 
 pub fn either<'a, P1, P2, A>(parser1: P1, parser2: P2) -> impl Parser<'a, A>
@@ -117,8 +335,542 @@ where
     P1: Parser<'a, A>,
     P2: Parser<'a, A>,
 {
-    move |input| match parser1.parse(input) {
-        ok @ Ok(_) => ok,
-        Err(_) => parser2.parse(input),
+    Either { parser1, parser2 }
+}
+
+/// Combine the errors of two branches that both failed trying to parse the
+/// same input. If they made equal progress, their `expected` labels are
+/// merged so the message reads "expected one of a, b, c"; otherwise the
+/// branch that got further (and is therefore more specific) wins.
+fn merge_errors<'a>(err1: ParseError<'a>, err2: ParseError<'a>) -> ParseError<'a> {
+    if err2.offset > err1.offset {
+        err2
+    } else if err1.offset > err2.offset {
+        err1
+    } else {
+        let mut expected = err1.expected;
+        for label in err2.expected {
+            if !expected.contains(&label) {
+                expected.push(label);
+            }
+        }
+
+        ParseError {
+            offset: err1.offset,
+            fragment: err1.fragment,
+            expected,
+            context: err1.context,
+            fatal: false,
+        }
+    }
+}
+
+pub struct Label<P> {
+    parser: P,
+    label: &'static str,
+}
+
+impl<'a, P, A> Parser<'a, A> for Label<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        self.parser.parse(input).map_err(|err| ParseError {
+            expected: vec![self.label],
+            ..err
+        })
+    }
+
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Tag a parser so its failure reports `expected = [label]` regardless of
+/// what the wrapped parser would have reported.
+pub fn label<'a, P, A>(parser: P, label: &'static str) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+{
+    Label { parser, label }
+}
+
+pub struct Context<P> {
+    parser: P,
+    context: &'static str,
+}
+
+impl<'a, P, A> Parser<'a, A> for Context<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        self.parser.parse(input).map_err(|mut err| {
+            err.context.push(self.context);
+            err
+        })
+    }
+
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Push a named context frame (e.g. "while parsing attribute") onto a
+/// parser's error if it fails.
+pub fn context<'a, P, A>(parser: P, context: &'static str) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+{
+    Context { parser, context }
+}
+
+pub struct ChoiceList<P> {
+    parsers: Vec<P>,
+}
+
+impl<'a, P, A> Parser<'a, A> for ChoiceList<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        let mut last_err: Option<ParseError<'a>> = None;
+
+        for parser in &self.parsers {
+            match parser.parse(input) {
+                ok @ Ok(_) => return ok,
+                Err(err) if err.fatal => return Err(err),
+                Err(err) => {
+                    last_err = Some(match last_err {
+                        Some(prev) => merge_errors(prev, err),
+                        None => err,
+                    });
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| ParseError::new(input, "one of the given alternatives")))
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Choice(self.parsers.iter().map(|p| p.representation()).collect())
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parsers.iter().flat_map(|p| p.productions()).collect()
+    }
+}
+
+/// Try each parser in turn against the same input, returning the first
+/// success. Generalizes `either` to any number of homogeneously-typed
+/// alternatives; see the `choice!` macro for heterogeneous ones.
+pub fn choice<'a, P, A>(parsers: Vec<P>) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+{
+    ChoiceList { parsers }
+}
+
+/// Try a variadic list of (possibly differently-typed) parsers in order,
+/// returning the first success. Expands to nested `either` calls.
+#[macro_export]
+macro_rules! choice {
+    ($first:expr $(,)?) => {
+        $first
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::combinators::either($first, $crate::choice!($($rest),+))
+    };
+}
+
+pub struct Optional<P> {
+    parser: P,
+}
+
+impl<'a, P, A> Parser<'a, Option<A>> for Optional<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, Option<A>> {
+        match self.parser.parse(input) {
+            Ok((result, next_input)) => Ok((Some(result), next_input)),
+            // A fatal failure (see `cut`) is a real error, not "absent".
+            Err(err) if err.fatal => Err(err),
+            Err(_) => Ok((None, input)),
+        }
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Optional(Box::new(self.parser.representation()))
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Make a parser's failure recoverable as `None` instead of propagating,
+/// without consuming any input on that path. A fatal failure (see `cut`)
+/// is still propagated.
+pub fn optional<'a, P, A>(parser: P) -> impl Parser<'a, Option<A>>
+where
+    P: Parser<'a, A>,
+{
+    Optional { parser }
+}
+
+pub struct SeparatedList1<P, S, B> {
+    item: P,
+    sep: S,
+    _sep_output: PhantomData<B>,
+}
+
+impl<'a, P, S, A, B> Parser<'a, Vec<A>> for SeparatedList1<P, S, B>
+where
+    P: Parser<'a, A>,
+    S: Parser<'a, B>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, Vec<A>> {
+        let (first, mut remaining) = self.item.parse(input)?;
+        let mut results = vec![first];
+
+        while let Ok((_, after_sep)) = self.sep.parse(remaining) {
+            match self.item.parse(after_sep) {
+                Ok((next, rest)) => {
+                    results.push(next);
+                    remaining = rest;
+                }
+                // A fatal failure means this item committed partway (e.g.
+                // via `cut`) before failing, so it's a real error, not "no
+                // more items". Shift it by how far earlier items and
+                // separators already advanced past `input`, or it would
+                // report a position from partway through this combinator's
+                // input instead of the original input.
+                Err(err) if err.fatal => return Err(err.shift(offset_between(input, after_sep))),
+                Err(_) => break,
+            }
+        }
+
+        Ok((results, remaining))
+    }
+
+    fn representation(&self) -> Representation {
+        let item = self.item.representation();
+        let sep = self.sep.representation();
+        Representation::Sequence(vec![
+            item.clone(),
+            Representation::Repeated(Box::new(Representation::Sequence(vec![sep, item]))),
+        ])
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        let mut productions = self.item.productions();
+        productions.extend(self.sep.productions());
+        productions
+    }
+}
+
+/// Parse `item (sep item)*`, requiring at least one `item`.
+pub fn separated_list1<'a, P, S, A, B>(item: P, sep: S) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A>,
+    S: Parser<'a, B>,
+{
+    SeparatedList1 {
+        item,
+        sep,
+        _sep_output: PhantomData,
+    }
+}
+
+/// Parse `item (sep item)*`, or an empty list if `item` doesn't match at all.
+pub fn separated_list0<'a, P, S, A, B>(item: P, sep: S) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A> + 'a,
+    S: Parser<'a, B> + 'a,
+    A: 'a,
+{
+    map(optional(separated_list1(item, sep)), |list| {
+        list.unwrap_or_default()
+    })
+}
+
+pub struct Recognize<P, A> {
+    parser: P,
+    _output: PhantomData<A>,
+}
+
+impl<'a, P, A> Parser<'a, &'a str> for Recognize<P, A>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, &'a str> {
+        let (_, next_input) = self.parser.parse(input)?;
+        let consumed = offset_between(input, next_input);
+        Ok((&input[..consumed], next_input))
+    }
+
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Run `parser` but yield the consumed substring instead of its output.
+pub fn recognize<'a, P, A>(parser: P) -> impl Parser<'a, &'a str>
+where
+    P: Parser<'a, A>,
+{
+    Recognize {
+        parser,
+        _output: PhantomData,
+    }
+}
+
+pub struct FoldMany0<P, Acc, F, A> {
+    parser: P,
+    init: Acc,
+    fold_fn: F,
+    _input: PhantomData<A>,
+}
+
+impl<'a, P, A, Acc, F> Parser<'a, Acc> for FoldMany0<P, Acc, F, A>
+where
+    P: Parser<'a, A>,
+    Acc: Clone,
+    F: Fn(Acc, A) -> Acc,
+{
+    fn parse(&self, mut input: &'a str) -> crate::parser::ParseResult<'a, Acc> {
+        let start = input;
+        let mut acc = self.init.clone();
+
+        loop {
+            match self.parser.parse(input) {
+                Ok((result, next_input)) => {
+                    acc = (self.fold_fn)(acc, result);
+                    input = next_input;
+                }
+                // Shift a fatal failure by how far earlier folds already
+                // advanced past `start`, or it would report a position
+                // from partway through this combinator's input instead of
+                // the original input.
+                Err(err) if err.fatal => return Err(err.shift(offset_between(start, input))),
+                Err(_) => break,
+            }
+        }
+
+        Ok((acc, input))
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Repeated(Box::new(self.parser.representation()))
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Fold zero or more matches of `parser` into an accumulator, starting from
+/// `init`, without collecting them into an intermediate `Vec` first.
+pub fn fold_many0<'a, P, A, Acc, F>(parser: P, init: Acc, fold_fn: F) -> impl Parser<'a, Acc>
+where
+    P: Parser<'a, A>,
+    Acc: Clone,
+    F: Fn(Acc, A) -> Acc,
+{
+    FoldMany0 {
+        parser,
+        init,
+        fold_fn,
+        _input: PhantomData,
+    }
+}
+
+pub struct Cut<P> {
+    parser: P,
+}
+
+impl<'a, P, A> Parser<'a, A> for Cut<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        self.parser.parse(input).map_err(ParseError::cut)
+    }
+
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+pub struct Describe<P> {
+    parser: P,
+    representation: Representation,
+}
+
+impl<'a, P, A> Parser<'a, A> for Describe<P>
+where
+    P: Parser<'a, A>,
+{
+    fn parse(&self, input: &'a str) -> crate::parser::ParseResult<'a, A> {
+        self.parser.parse(input)
+    }
+
+    fn representation(&self) -> Representation {
+        self.representation.clone()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// Override a parser's `representation` for `grammar()` without changing
+/// what it parses. Exists for combinators like `and_then` whose shape
+/// can't be computed generically (see `AndThen::representation`) but
+/// whose caller can still state it by hand. See `Parser::describe`.
+pub fn describe<'a, P, A>(parser: P, representation: Representation) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+{
+    Describe {
+        parser,
+        representation,
+    }
+}
+
+/// Mark a parser as a commit point: a failure it produces is no longer
+/// recoverable, so an enclosing `either`/`choice` won't try a sibling
+/// alternative against the same input. See `Parser::cut`.
+pub fn cut<'a, P, A>(parser: P) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+{
+    Cut { parser }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xml::{any_char, match_literal};
+
+    fn digit<'a>() -> impl Parser<'a, char> {
+        pred(any_char, |c| c.is_ascii_digit())
+    }
+
+    #[test]
+    fn choice_fn() {
+        let parser = choice(vec![match_literal("a"), match_literal("b")]);
+        assert_eq!(Ok(((), "")), parser.parse("a"));
+        assert_eq!(Ok(((), "")), parser.parse("b"));
+        assert!(parser.parse("c").is_err());
+    }
+
+    #[test]
+    fn choice_macro() {
+        let parser = choice!(match_literal("a"), match_literal("b"), match_literal("c"));
+        assert_eq!(Ok(((), "")), parser.parse("c"));
+        assert!(parser.parse("d").is_err());
+    }
+
+    #[test]
+    fn optional_comb() {
+        let parser = optional(match_literal("a"));
+        assert_eq!(Ok((Some(()), "b")), parser.parse("ab"));
+        assert_eq!(Ok((None, "b")), parser.parse("b"));
+    }
+
+    #[test]
+    fn separated_list1_comb() {
+        let parser = separated_list1(digit(), match_literal(","));
+        assert_eq!(Ok((vec!['1', '2', '3'], "")), parser.parse("1,2,3"));
+        assert!(parser.parse("").is_err());
+    }
+
+    #[test]
+    fn separated_list0_comb() {
+        let parser = separated_list0(digit(), match_literal(","));
+        assert_eq!(Ok((vec!['1', '2', '3'], "")), parser.parse("1,2,3"));
+        assert_eq!(Ok((Vec::new(), "x")), parser.parse("x"));
+    }
+
+    #[test]
+    fn recognize_comb() {
+        let parser = recognize(pair(match_literal("a"), one_or_more(digit())));
+        assert_eq!(Ok(("a123", "")), parser.parse("a123"));
+    }
+
+    #[test]
+    fn fold_many0_comb() {
+        let parser = fold_many0(digit(), 0u32, |acc, c| acc * 10 + c.to_digit(10).unwrap());
+        assert_eq!(Ok((123, "")), parser.parse("123"));
+        assert_eq!(Ok((0, "abc")), parser.parse("abc"));
+    }
+
+    #[test]
+    fn cut_marks_error_fatal() {
+        let parser = cut(match_literal("a"));
+        let err = parser.parse("b").unwrap_err();
+        assert!(err.fatal);
+    }
+
+    #[test]
+    fn either_skips_second_branch_after_cut() {
+        let parser = either(right(match_literal("a"), cut(match_literal("b"))), match_literal("ac"));
+        // `a` matches the first branch, committing to it; the cut failure
+        // on `b` must propagate instead of letting the second branch retry.
+        let err = parser.parse("ac").unwrap_err();
+        assert!(err.fatal);
+    }
+
+    #[test]
+    fn separated_list1_propagates_fatal_error_from_later_item() {
+        let item = pair(match_literal("a"), cut(match_literal("!"))).map(|_| ());
+        let parser = separated_list1(item, match_literal(","));
+        // The second item commits on `a` and cuts on `!`; failing to match
+        // it must be a real error, not "end of list". Its offset must be
+        // relative to the whole input, not just the second item's slice
+        // (`"a!,a?"` is 5 bytes; the `!` cut fails against `"?"` at byte 4).
+        let err = parser.parse("a!,a?").unwrap_err();
+        assert!(err.fatal);
+        assert_eq!(err.offset, 4);
+    }
+
+    #[test]
+    fn one_or_more_fatal_error_offset_is_relative_to_original_input() {
+        let item = pair(match_literal("a"), cut(match_literal("!"))).map(|_| ());
+        let parser = one_or_more(item);
+        // The first "a!" matches and advances past 2 bytes; the second
+        // repetition commits on `a` and cuts on `!`, failing against `"?"`
+        // at byte 3 of the original input, not byte 1 of its own slice.
+        let err = parser.parse("a!a?").unwrap_err();
+        assert!(err.fatal);
+        assert_eq!(err.offset, 3);
+    }
+
+    #[test]
+    fn and_then_error_offset_is_relative_to_original_input() {
+        let parser = match_literal("a").and_then(|_| cut(match_literal("!")));
+        // The continuation fails against `"b"`, which starts at byte 1 of
+        // the original input, not byte 0 of the slice it's handed.
+        let err = parser.parse("ab").unwrap_err();
+        assert!(err.fatal);
+        assert_eq!(err.offset, 1);
     }
 }
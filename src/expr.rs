@@ -0,0 +1,281 @@
+use crate::parser::{offset_between, BoxedParser, ParseResult, Parser};
+
+/// Which side a left-recursive infix operator folds toward.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+/// One entry in an infix operator table: how to recognize the operator,
+/// how tightly it binds relative to its neighbors, and which side it
+/// associates to. Construct with `InfixOp::new`.
+pub struct InfixOp<'a, Op> {
+    matches: BoxedParser<'a, Op>,
+    binding_power: u8,
+    assoc: Assoc,
+}
+
+impl<'a, Op> InfixOp<'a, Op> {
+    pub fn new<P>(matches: P, binding_power: u8, assoc: Assoc) -> Self
+    where
+        P: Parser<'a, Op> + 'a,
+        Op: 'a,
+    {
+        Self {
+            matches: BoxedParser::new(matches),
+            binding_power,
+            assoc,
+        }
+    }
+}
+
+/// A prefix or postfix operator. Recognizing it yields the transformation
+/// it applies to its single operand, so unlike `InfixOp` it needs no
+/// separate reducer: the operator's own parser, via `.map`, already knows
+/// how to turn itself into an `Expr -> Expr` function.
+pub struct UnaryOp<'a, Expr> {
+    matches: BoxedParser<'a, Box<dyn Fn(Expr) -> Expr + 'a>>,
+    binding_power: u8,
+}
+
+impl<'a, Expr> UnaryOp<'a, Expr> {
+    pub fn new<P>(matches: P, binding_power: u8, apply: impl Fn(Expr) -> Expr + Clone + 'a) -> Self
+    where
+        P: Parser<'a, ()> + 'a,
+        Expr: 'a,
+    {
+        Self {
+            matches: BoxedParser::new(
+                matches.map(move |_| Box::new(apply.clone()) as Box<dyn Fn(Expr) -> Expr>),
+            ),
+            binding_power,
+        }
+    }
+}
+
+/// A precedence-climbing (Pratt) expression parser, built from a `term`
+/// parser for atoms plus tables of prefix, infix, and postfix operators.
+/// Build one with `pratt` and optionally `with_prefix`/`with_postfix`.
+pub struct Pratt<'a, TermP, Expr, Op, Reduce> {
+    term: TermP,
+    prefix: Vec<UnaryOp<'a, Expr>>,
+    infix: Vec<InfixOp<'a, Op>>,
+    postfix: Vec<UnaryOp<'a, Expr>>,
+    reduce: Reduce,
+}
+
+/// Build a precedence-climbing expression parser: `term` recognizes the
+/// atoms, `infix` is the operator table, and `reduce` folds a matched
+/// operator and its two operands into a new `Expr`.
+///
+/// Add prefix/postfix operators with `with_prefix`/`with_postfix` before
+/// using the result as a `Parser`.
+pub fn pratt<'a, TermP, Expr, Op, Reduce>(
+    term: TermP,
+    infix: Vec<InfixOp<'a, Op>>,
+    reduce: Reduce,
+) -> Pratt<'a, TermP, Expr, Op, Reduce>
+where
+    TermP: Parser<'a, Expr>,
+    Reduce: Fn(Op, Expr, Expr) -> Expr,
+{
+    Pratt {
+        term,
+        prefix: Vec::new(),
+        infix,
+        postfix: Vec::new(),
+        reduce,
+    }
+}
+
+impl<'a, TermP, Expr, Op, Reduce> Pratt<'a, TermP, Expr, Op, Reduce> {
+    pub fn with_prefix(mut self, prefix: Vec<UnaryOp<'a, Expr>>) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    pub fn with_postfix(mut self, postfix: Vec<UnaryOp<'a, Expr>>) -> Self {
+        self.postfix = postfix;
+        self
+    }
+}
+
+impl<'a, TermP, Expr, Op, Reduce> Pratt<'a, TermP, Expr, Op, Reduce>
+where
+    TermP: Parser<'a, Expr>,
+    Reduce: Fn(Op, Expr, Expr) -> Expr,
+{
+    /// Parse a (possibly prefix-operated) atom: the left-hand side that
+    /// `parse_bp`'s loop then extends with infix/postfix operators.
+    fn parse_lhs(&self, input: &'a str) -> ParseResult<'a, Expr> {
+        for op in &self.prefix {
+            if let Ok((apply, after_op)) = op.matches.parse(input) {
+                let (operand, rest) = self
+                    .parse_bp(after_op, op.binding_power)
+                    .map_err(|err| err.shift(offset_between(input, after_op)))?;
+                return Ok((apply(operand), rest));
+            }
+        }
+
+        self.term.parse(input)
+    }
+
+    /// The precedence-climbing loop: extend `lhs` with postfix and infix
+    /// operators whose binding power clears `min_bp`, stopping cleanly
+    /// (returning the current `lhs`) the first time none does. An
+    /// operator's token is never consumed until its binding power has
+    /// already cleared the threshold.
+    fn parse_bp(&self, input: &'a str, min_bp: u8) -> ParseResult<'a, Expr> {
+        let (mut lhs, mut rest) = self.parse_lhs(input)?;
+
+        'climb: loop {
+            for op in &self.postfix {
+                if op.binding_power < min_bp {
+                    continue;
+                }
+                if let Ok((apply, after_op)) = op.matches.parse(rest) {
+                    lhs = apply(lhs);
+                    rest = after_op;
+                    continue 'climb;
+                }
+            }
+
+            let mut matched = None;
+            for op in &self.infix {
+                if op.binding_power < min_bp {
+                    continue;
+                }
+                if let Ok((value, after_op)) = op.matches.parse(rest) {
+                    matched = Some((op, value, after_op));
+                    break;
+                }
+            }
+
+            let Some((op, value, after_op)) = matched else {
+                break;
+            };
+
+            let next_min_bp = match op.assoc {
+                Assoc::Left => op.binding_power + 1,
+                Assoc::Right => op.binding_power,
+            };
+
+            let (rhs, after_rhs) = self
+                .parse_bp(after_op, next_min_bp)
+                .map_err(|err| err.shift(offset_between(input, after_op)))?;
+            lhs = (self.reduce)(value, lhs, rhs);
+            rest = after_rhs;
+        }
+
+        Ok((lhs, rest))
+    }
+}
+
+impl<'a, TermP, Expr, Op, Reduce> Parser<'a, Expr> for Pratt<'a, TermP, Expr, Op, Reduce>
+where
+    TermP: Parser<'a, Expr>,
+    Reduce: Fn(Op, Expr, Expr) -> Expr,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<'a, Expr> {
+        self.parse_bp(input, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combinators::pred;
+    use crate::xml::{any_char, match_literal};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Op {
+        Add,
+        Sub,
+        Mul,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Expr {
+        Num(u32),
+        Bin(Op, Box<Expr>, Box<Expr>),
+        Neg(Box<Expr>),
+    }
+
+    fn num<'a>() -> impl Parser<'a, Expr> {
+        pred(any_char, |c| c.is_ascii_digit())
+            .map(|c| Expr::Num(c.to_digit(10).unwrap()))
+    }
+
+    fn calculator<'a>() -> impl Parser<'a, Expr> {
+        pratt(
+            num(),
+            vec![
+                InfixOp::new(match_literal("+").map(|_| Op::Add), 1, Assoc::Left),
+                InfixOp::new(match_literal("-").map(|_| Op::Sub), 1, Assoc::Left),
+                InfixOp::new(match_literal("*").map(|_| Op::Mul), 2, Assoc::Left),
+            ],
+            |op, lhs, rhs| Expr::Bin(op, Box::new(lhs), Box::new(rhs)),
+        )
+        .with_prefix(vec![UnaryOp::new(
+            match_literal("-"),
+            3,
+            |e| Expr::Neg(Box::new(e)),
+        )])
+    }
+
+    #[test]
+    fn left_associative_same_precedence() {
+        assert_eq!(
+            Ok((
+                Expr::Bin(
+                    Op::Sub,
+                    Box::new(Expr::Bin(Op::Add, Box::new(Expr::Num(1)), Box::new(Expr::Num(2)))),
+                    Box::new(Expr::Num(3)),
+                ),
+                "",
+            )),
+            calculator().parse("1+2-3"),
+        );
+    }
+
+    #[test]
+    fn higher_precedence_binds_tighter() {
+        assert_eq!(
+            Ok((
+                Expr::Bin(
+                    Op::Add,
+                    Box::new(Expr::Num(1)),
+                    Box::new(Expr::Bin(Op::Mul, Box::new(Expr::Num(2)), Box::new(Expr::Num(3)))),
+                ),
+                "",
+            )),
+            calculator().parse("1+2*3"),
+        );
+    }
+
+    #[test]
+    fn prefix_operator() {
+        assert_eq!(
+            Ok((
+                Expr::Bin(
+                    Op::Add,
+                    Box::new(Expr::Neg(Box::new(Expr::Num(1)))),
+                    Box::new(Expr::Num(2)),
+                ),
+                "",
+            )),
+            calculator().parse("-1+2"),
+        );
+    }
+
+    #[test]
+    fn single_term_no_operator() {
+        assert_eq!(Ok((Expr::Num(4), "")), calculator().parse("4"));
+    }
+
+    #[test]
+    fn stops_before_unmatched_trailing_input() {
+        assert_eq!(Ok((Expr::Num(1), ";")), calculator().parse("1;"));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::choice;
+use crate::combinators::*;
+use crate::parser::{BoxedParser, Parser};
+use crate::xml::{any_char, match_char, match_literal, quoted_string, whitespace_wrap};
+use std::fmt;
+
+/// A parsed JSON value. Numbers are always stored as `f64`, matching the
+/// JSON spec's single numeric type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::Number(n) => write!(f, "{}", n),
+            JsonValue::String(s) => write!(f, "{:?}", s),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{:?}:{}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn json_null<'a>() -> impl Parser<'a, JsonValue> {
+    match_literal("null").map(|_| JsonValue::Null)
+}
+
+fn json_bool<'a>() -> impl Parser<'a, JsonValue> {
+    choice!(
+        match_literal("true").map(|_| JsonValue::Bool(true)),
+        match_literal("false").map(|_| JsonValue::Bool(false)),
+    )
+}
+
+fn digits1<'a>() -> impl Parser<'a, Vec<char>> {
+    one_or_more(pred(any_char, |c| c.is_ascii_digit()))
+}
+
+fn fraction<'a>() -> impl Parser<'a, Option<((), Vec<char>)>> {
+    optional(pair(match_char('.'), digits1()))
+}
+
+type Exponent = Option<(char, (Option<char>, Vec<char>))>;
+
+fn exponent<'a>() -> impl Parser<'a, Exponent> {
+    optional(pair(
+        pred(any_char, |c| *c == 'e' || *c == 'E'),
+        pair(optional(pred(any_char, |c| *c == '+' || *c == '-')), digits1()),
+    ))
+}
+
+/// A number literal: optional sign, an integer part, an optional fraction,
+/// and an optional exponent. The whole span is recognized first and handed
+/// to `str::parse` rather than accumulated digit by digit, since the
+/// grammar above already guarantees it's a valid `f64` literal.
+fn json_number<'a>() -> impl Parser<'a, JsonValue> {
+    recognize(pair(
+        optional(match_char('-')),
+        pair(digits1(), pair(fraction(), exponent())),
+    ))
+    .map(|text: &str| JsonValue::Number(text.parse().expect("grammar guarantees a valid f64 literal")))
+}
+
+fn json_string<'a>() -> impl Parser<'a, JsonValue> {
+    quoted_string().map(JsonValue::String)
+}
+
+fn json_array<'a>() -> impl Parser<'a, JsonValue> {
+    right(
+        match_literal("["),
+        left(
+            separated_list0(
+                BoxedParser::new(|input| json_value().parse(input)),
+                match_literal(","),
+            ),
+            match_literal("]"),
+        ),
+    )
+    .map(JsonValue::Array)
+}
+
+fn json_member<'a>() -> impl Parser<'a, (String, JsonValue)> {
+    pair(
+        whitespace_wrap(quoted_string()),
+        right(
+            whitespace_wrap(match_literal(":")),
+            BoxedParser::new(|input| json_value().parse(input)),
+        ),
+    )
+}
+
+fn json_object<'a>() -> impl Parser<'a, JsonValue> {
+    right(
+        match_literal("{"),
+        left(
+            separated_list0(json_member(), match_literal(",")),
+            match_literal("}"),
+        ),
+    )
+    .map(JsonValue::Object)
+}
+
+/// Parse any JSON value. `json_array` and `json_member` call back into
+/// this function through a `BoxedParser`, the same way `xml`'s
+/// `parent_element` boxes its call back into `element`, to break the
+/// infinitely-sized opaque return type.
+pub fn json_value<'a>() -> impl Parser<'a, JsonValue> {
+    whitespace_wrap(choice!(
+        json_object(),
+        json_array(),
+        json_string(),
+        json_number(),
+        json_bool(),
+        json_null(),
+    ))
+    .name("value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn null() {
+        assert_eq!(Ok((JsonValue::Null, "")), json_value().parse("null"));
+    }
+
+    #[test]
+    fn bools() {
+        assert_eq!(Ok((JsonValue::Bool(true), "")), json_value().parse("true"));
+        assert_eq!(Ok((JsonValue::Bool(false), "")), json_value().parse("false"));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Ok((JsonValue::Number(42.0), "")), json_value().parse("42"));
+        assert_eq!(Ok((JsonValue::Number(-3.5), "")), json_value().parse("-3.5"));
+        assert_eq!(
+            Ok((JsonValue::Number(1.5e10), "")),
+            json_value().parse("1.5e10")
+        );
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(
+            Ok((JsonValue::String("hi there".to_owned()), "")),
+            json_value().parse(r#""hi there""#)
+        );
+    }
+
+    #[test]
+    fn empty_array() {
+        assert_eq!(Ok((JsonValue::Array(vec![]), "")), json_value().parse("[]"));
+    }
+
+    #[test]
+    fn array_of_numbers() {
+        assert_eq!(
+            Ok((
+                JsonValue::Array(vec![
+                    JsonValue::Number(1.0),
+                    JsonValue::Number(2.0),
+                    JsonValue::Number(3.0),
+                ]),
+                "",
+            )),
+            json_value().parse("[1, 2, 3]")
+        );
+    }
+
+    #[test]
+    fn empty_object() {
+        assert_eq!(
+            Ok((JsonValue::Object(vec![]), "")),
+            json_value().parse("{}")
+        );
+    }
+
+    #[test]
+    fn nested_object() {
+        let doc = r#"{ "name": "parcom", "tags": ["parser", "combinator"], "stable": false }"#;
+        let parsed = JsonValue::Object(vec![
+            ("name".to_owned(), JsonValue::String("parcom".to_owned())),
+            (
+                "tags".to_owned(),
+                JsonValue::Array(vec![
+                    JsonValue::String("parser".to_owned()),
+                    JsonValue::String("combinator".to_owned()),
+                ]),
+            ),
+            ("stable".to_owned(), JsonValue::Bool(false)),
+        ]);
+        assert_eq!(Ok((parsed, "")), json_value().parse(doc));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(json_value().parse("not json").is_err());
+    }
+}
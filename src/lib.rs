@@ -1,3 +1,10 @@
+pub mod combinators;
+pub mod expr;
+pub mod json;
+pub mod parser;
+pub mod representation;
+pub mod xml;
+
 pub type Result<T> = std::result::Result<(String, T), String>;
 
 pub fn letter_a(input: String) -> Result<()> {
@@ -1,10 +1,128 @@
 use crate::combinators::*;
+use crate::representation::Representation;
+use std::fmt;
 
-pub type ParseResult<'a, Output> = std::result::Result<(Output, &'a str), &'a str>;
+pub type ParseResult<'a, Output> = std::result::Result<(Output, &'a str), ParseError<'a>>;
+
+/// A structured parse failure: where it happened in the original input,
+/// what was expected there, and the stack of named contexts ("while
+/// parsing attribute") that were active when it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError<'a> {
+    /// Byte offset into the original input, relative to whatever `&str`
+    /// this error has been propagated up to so far. By the time an error
+    /// reaches the caller of the top-level `parse`, this is the offset
+    /// into the original document.
+    pub offset: usize,
+    /// The input fragment at the point of failure, kept around for
+    /// rendering a snippet of context in error messages.
+    pub fragment: &'a str,
+    pub expected: Vec<&'static str>,
+    pub context: Vec<&'static str>,
+    /// Whether this error is a commit-point failure (see `Parser::cut`):
+    /// once set, an enclosing `either`/`choice` won't try a sibling
+    /// alternative, but will propagate this error as-is instead.
+    pub fatal: bool,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(fragment: &'a str, expected: &'static str) -> Self {
+        Self {
+            offset: 0,
+            fragment,
+            expected: vec![expected],
+            context: Vec::new(),
+            fatal: false,
+        }
+    }
+
+    /// Shift this error's offset by `delta` bytes, used when a combinator
+    /// propagates a failure from a sub-parser it called partway through
+    /// its own input.
+    pub fn shift(mut self, delta: usize) -> Self {
+        self.offset += delta;
+        self
+    }
+
+    /// Mark this error as fatal, as `Parser::cut` does to whatever error
+    /// its wrapped parser produces.
+    pub fn cut(mut self) -> Self {
+        self.fatal = true;
+        self
+    }
+}
+
+/// Byte distance between `start` and `end`, computed by pointer arithmetic.
+/// Only meaningful when `end` is a suffix slice of `start` (as is always
+/// the case here, since every combinator narrows `&str`s by slicing rather
+/// than reallocating).
+pub fn offset_between(start: &str, end: &str) -> usize {
+    end.as_ptr() as usize - start.as_ptr() as usize
+}
+
+impl<'a> fmt::Display for ParseError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expected.len() {
+            0 => write!(f, "parse error at byte {}", self.offset)?,
+            1 => write!(
+                f,
+                "parse error at byte {}: expected {}",
+                self.offset, self.expected[0]
+            )?,
+            _ => write!(
+                f,
+                "parse error at byte {}: expected one of {}",
+                self.offset,
+                self.expected.join(", ")
+            )?,
+        }
+
+        for ctx in &self.context {
+            write!(f, "\n  {}", ctx)?;
+        }
+
+        Ok(())
+    }
+}
 
 pub trait Parser<'a, Output> {
     fn parse(&self, input: &'a str) -> ParseResult<'a, Output>;
 
+    /// The EBNF shape this parser recognizes. Combinators that have a
+    /// natural grammar shape (sequencing, alternation, repetition, ...)
+    /// override this; anything without structural information to report
+    /// (e.g. a bare closure) falls back to this default of "some unnamed
+    /// terminal". `and_then` reports only its first parser's shape.
+    fn representation(&self) -> Representation {
+        Representation::Terminal("?".to_string())
+    }
+
+    /// Named productions (from `Parser::name`) reachable from this parser,
+    /// collected so `Grammar`'s `Display` can print them once each instead
+    /// of inlining their expansion everywhere they're referenced.
+    fn productions(&self) -> Vec<(String, Representation)> {
+        Vec::new()
+    }
+
+    /// The full EBNF grammar this parser recognizes: its own shape plus
+    /// every named production (`Parser::name`) reachable from it.
+    fn grammar(&self) -> crate::representation::Grammar {
+        crate::representation::Grammar::new(self.representation(), self.productions())
+    }
+
+    /// Wrap this parser as a named nonterminal: its own representation
+    /// becomes a bare `Nonterminal(name)` reference (which is what stops
+    /// self-referential grammars, like an XML element containing elements,
+    /// from expanding forever), while its expansion is recorded so the
+    /// full grammar can still print `name = ... ;` for it.
+    fn name(self, name: &'static str) -> Named<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+    {
+        Named::new(self, name)
+    }
+
     fn map<F, NewOutput>(self, map_fn: F) -> BoxedParser<'a, NewOutput>
     where
         Self: Sized + 'a,
@@ -34,6 +152,52 @@ pub trait Parser<'a, Output> {
     {
         BoxedParser::new(and_then(self, f))
     }
+
+    /// Tag this parser so that a failure reports `expected = [label]`
+    /// instead of whatever label the wrapped parser produced.
+    fn label(self, label: &'static str) -> BoxedParser<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+    {
+        BoxedParser::new(crate::combinators::label(self, label))
+    }
+
+    /// Push a named frame (e.g. "while parsing attribute") onto the error's
+    /// context stack if this parser fails.
+    fn context(self, ctx: &'static str) -> BoxedParser<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+    {
+        BoxedParser::new(crate::combinators::context(self, ctx))
+    }
+
+    /// Mark this parser as a commit point: a failure here means the input
+    /// matched far enough that it's no longer ambiguous which alternative
+    /// was intended, so the failure is made fatal. An enclosing
+    /// `either`/`choice`/`choice!` propagates a fatal failure instead of
+    /// trying its next alternative against the same input.
+    fn cut(self) -> BoxedParser<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+    {
+        BoxedParser::new(crate::combinators::cut(self))
+    }
+
+    /// Override this parser's `representation` for `grammar()`, without
+    /// changing what it parses. `and_then`'s continuation parser can't
+    /// report its own shape generically (it only exists once a runtime
+    /// value is available), so a caller that knows that shape up front
+    /// anyway can restate it here instead of leaving it invisible.
+    fn describe(self, representation: Representation) -> BoxedParser<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+    {
+        BoxedParser::new(crate::combinators::describe(self, representation))
+    }
 }
 
 impl<'a, F, Output> Parser<'a, Output> for F
@@ -50,7 +214,7 @@ pub struct BoxedParser<'a, Output> {
 }
 
 impl<'a, Output> BoxedParser<'a, Output> {
-    fn new<P>(parser: P) -> Self
+    pub(crate) fn new<P>(parser: P) -> Self
     where
         P: Parser<'a, Output> + 'a,
     {
@@ -64,5 +228,51 @@ impl<'a, Output> Parser<'a, Output> for BoxedParser<'a, Output> {
     fn parse(&self, input: &'a str) -> ParseResult<'a, Output> {
         self.parser.parse(input)
     }
+
+    fn representation(&self) -> Representation {
+        self.parser.representation()
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        self.parser.productions()
+    }
+}
+
+/// A parser wrapped by `Parser::name`. See that method for why this is the
+/// boundary that keeps a self-referential grammar's EBNF dump finite.
+pub struct Named<'a, Output> {
+    name: &'static str,
+    expansion: Representation,
+    inner: BoxedParser<'a, Output>,
+}
+
+impl<'a, Output> Named<'a, Output> {
+    fn new<P>(parser: P, name: &'static str) -> Self
+    where
+        P: Parser<'a, Output> + 'a,
+    {
+        let expansion = parser.representation();
+        Self {
+            name,
+            expansion,
+            inner: BoxedParser::new(parser),
+        }
+    }
+}
+
+impl<'a, Output> Parser<'a, Output> for Named<'a, Output> {
+    fn parse(&self, input: &'a str) -> ParseResult<'a, Output> {
+        self.inner.parse(input)
+    }
+
+    fn representation(&self) -> Representation {
+        Representation::Nonterminal(self.name.to_string())
+    }
+
+    fn productions(&self) -> Vec<(String, Representation)> {
+        let mut productions = self.inner.productions();
+        productions.push((self.name.to_string(), self.expansion.clone()));
+        productions
+    }
 }
 
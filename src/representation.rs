@@ -0,0 +1,100 @@
+use std::fmt;
+
+/// A node in the EBNF grammar tree a composed parser can describe itself
+/// with, via `Parser::representation`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Representation {
+    Terminal(String),
+    Nonterminal(String),
+    Sequence(Vec<Representation>),
+    Choice(Vec<Representation>),
+    Repeated(Box<Representation>),
+    Optional(Box<Representation>),
+}
+
+impl Representation {
+    /// Whether this node needs parens when nested inside a `Sequence` or
+    /// `Choice` sibling list, to avoid ambiguous `a | b c | d` style output.
+    fn needs_parens_in(&self, parent: &Representation) -> bool {
+        matches!(
+            (parent, self),
+            (Representation::Sequence(_), Representation::Choice(_))
+                | (Representation::Choice(_), Representation::Sequence(_))
+        )
+    }
+
+    fn fmt_child(&self, parent: &Representation, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.needs_parens_in(parent) {
+            write!(f, "( {} )", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Representation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Representation::Terminal(literal) => write!(f, "{}", literal),
+            Representation::Nonterminal(name) => write!(f, "{}", name),
+            Representation::Sequence(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    part.fmt_child(self, f)?;
+                }
+                Ok(())
+            }
+            Representation::Choice(branches) => {
+                for (i, branch) in branches.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    branch.fmt_child(self, f)?;
+                }
+                Ok(())
+            }
+            Representation::Repeated(inner) => write!(f, "{{ {} }}", inner),
+            Representation::Optional(inner) => write!(f, "[ {} ]", inner),
+        }
+    }
+}
+
+/// The full grammar a composed parser describes: its own shape, plus the
+/// named productions (from `Parser::name`) reachable from it. A named
+/// production is only expanded once, here, at the top level; any reference
+/// to it elsewhere in the tree is printed as a bare name, which is what
+/// keeps self-referential rules (e.g. an XML element containing elements)
+/// from being printed as an infinite tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grammar {
+    pub root: Representation,
+    pub productions: Vec<(String, Representation)>,
+}
+
+impl Grammar {
+    /// Build a `Grammar`, deduplicating `productions` by name (keeping
+    /// the first occurrence). A production can be collected more than
+    /// once from `Parser::productions` — e.g. `identifier` is referenced
+    /// from the start tag of both a self-closing and a parent element — and without
+    /// this it would print once per reference instead of once overall.
+    pub fn new(root: Representation, productions: Vec<(String, Representation)>) -> Self {
+        let mut seen = std::collections::HashSet::new();
+        let productions = productions
+            .into_iter()
+            .filter(|(name, _)| seen.insert(name.clone()))
+            .collect();
+        Self { root, productions }
+    }
+}
+
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ;", self.root)?;
+        for (name, expansion) in &self.productions {
+            write!(f, "\n{} = {} ;", name, expansion)?;
+        }
+        Ok(())
+    }
+}
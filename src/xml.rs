@@ -1,5 +1,6 @@
-use crate::parser::{Parser, ParseResult};
+use crate::parser::{BoxedParser, ParseError, ParseResult, Parser};
 use crate::combinators::*;
+use crate::representation::Representation;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Element {
@@ -11,18 +12,30 @@ pub struct Element {
 pub fn any_char(input: &str) -> ParseResult<'_, char> {
     match input.chars().next() {
         Some(next) => Ok((next, &input[next.len_utf8()..])),
-        _ => Err(input),
+        _ => Err(ParseError::new(input, "any character")),
     }
 }
 
-pub fn match_literal<'a>(expected: &'static str) -> impl Parser<'a, ()> {
-    move |input: &'a str| {
-        if let Some(rest) = input.strip_prefix(expected) {
+struct MatchLiteral {
+    expected: &'static str,
+}
+
+impl<'a> Parser<'a, ()> for MatchLiteral {
+    fn parse(&self, input: &'a str) -> ParseResult<'a, ()> {
+        if let Some(rest) = input.strip_prefix(self.expected) {
             Ok(((), rest))
         } else {
-            Err(input)
+            Err(ParseError::new(input, self.expected))
         }
     }
+
+    fn representation(&self) -> Representation {
+        Representation::Terminal(format!("{:?}", self.expected))
+    }
+}
+
+pub fn match_literal<'a>(expected: &'static str) -> impl Parser<'a, ()> {
+    MatchLiteral { expected }
 }
 
 pub fn whitespace_char<'a>() -> impl Parser<'a, char> {
@@ -59,7 +72,7 @@ pub fn self_closing_element<'a>() -> impl Parser<'a, Element> {
 pub fn match_char<'a>(ch: char) -> impl Parser<'a, ()> {
     move |input: &'a str| match input.chars().next() {
         Some(c) if c == ch => Ok(((), &input[ch.len_utf8()..])),
-        _ => Err(input),
+        _ => Err(ParseError::new(input, "a matching character")),
     }
 }
 
@@ -69,7 +82,7 @@ pub fn identifier(input: &str) -> ParseResult<String> {
 
     match chars.next() {
         Some(next) if next.is_alphabetic() => matched.push(next),
-        _ => return Err(input),
+        _ => return Err(ParseError::new(input, "identifier")),
     }
 
     for ch in chars {
@@ -98,7 +111,13 @@ pub fn quoted_string<'a>() -> impl Parser<'a, String> {
 }
 
 pub fn attribute_pair<'a>() -> impl Parser<'a, (String, String)> {
-    pair(identifier, right(match_literal("="), quoted_string()))
+    // Once an identifier has matched, this can only be an attribute: a
+    // missing `=` or malformed value is cut, so `attributes()`'s repetition
+    // propagates the failure instead of quietly treating it as "no more
+    // attributes".
+    pair(identifier.name("identifier"), right(match_literal("="), quoted_string()).cut())
+        .context("while parsing an attribute")
+        .name("attribute")
 }
 
 pub fn attributes<'a>() -> impl Parser<'a, Vec<(String, String)>> {
@@ -106,38 +125,77 @@ pub fn attributes<'a>() -> impl Parser<'a, Vec<(String, String)>> {
 }
 
 pub fn element_start<'a>() -> impl Parser<'a, (String, Vec<(String, String)>)> {
-    right(match_literal("<"), pair(identifier, attributes()))
+    right(match_literal("<"), pair(identifier.name("identifier"), attributes()))
+        .context("while parsing an element's start tag")
 }
 
 pub fn open_element<'a>() -> impl Parser<'a, Element> {
-    whitespace_wrap(left(element_start(), left(space0(), match_literal(">"))).map(|(name, attributes)| Element {
-        name,
-        attributes,
-        children: vec![],
-    }))
+    whitespace_wrap(
+        left(element_start(), left(space0(), match_literal(">")).cut()).map(|(name, attributes)| Element {
+            name,
+            attributes,
+            children: vec![],
+        }),
+    )
 }
 
 pub fn single_element<'a>() -> impl Parser<'a, Element> {
-    whitespace_wrap(either(self_closing_element(), open_element()))
+    // Only the self-closing form counts as a complete, childless element:
+    // a bare `open_element()` is just the first half of a parent element
+    // and must not be accepted on its own, or `element()`'s alternation
+    // below would never reach `parent_element()` for anything with children.
+    //
+    // This drops the `open_element()` branch this parser used to also
+    // accept alongside `self_closing_element()`: that branch let
+    // `element()` wrongly terminate on a bare open tag, so nesting (e.g.
+    // `<top><middle>...`) never reached `parent_element()` and never
+    // closed. It's a behavior fix, not a side effect of the error
+    // handling in this commit.
+    whitespace_wrap(self_closing_element()).name("single_element")
 }
 
 pub fn close_element<'a>(expected_name: String) -> impl Parser<'a, String> {
-    whitespace_wrap(right(match_literal("</"), left(identifier, match_literal(">")))
-        .pred(move |name| name == &expected_name))
+    whitespace_wrap(
+        right(match_literal("</"), left(identifier, match_literal(">")))
+            .pred(move |name| name == &expected_name)
+            .context("while parsing a closing tag"),
+    )
 }
 
 pub fn parent_element<'a>() -> impl Parser<'a, Element> {
-    whitespace_wrap(open_element().and_then(|element| {
-        left(
-            zero_or_more(single_element()),
-            close_element(element.name.clone()),
-        )
-        .map(move |children| {
-            let mut element = element.clone();
-            element.children = children;
-            element
-        })
-    }))
+    // `open_element().and_then(...)` can only report the `open_element()`
+    // half of its own shape (see `AndThen::representation`): the
+    // continuation isn't a value until an element name has actually been
+    // parsed. That continuation's *shape* doesn't depend on the name
+    // though, so it's restated here via `.describe(...)`, built from a
+    // `close_element` called with a placeholder name purely to read its
+    // representation off of.
+    let continuation = Representation::Sequence(vec![
+        Representation::Repeated(Box::new(Representation::Nonterminal("element".to_string()))),
+        close_element(String::new()).representation(),
+    ]);
+    let shape = Representation::Sequence(vec![open_element().representation(), continuation]);
+
+    whitespace_wrap(
+        open_element()
+            .and_then(|el| {
+                left(
+                    // `element()` recurses back through `parent_element()`, so its
+                    // opaque return type can't be embedded here directly without an
+                    // infinitely-sized type; boxing it erases that into a trait
+                    // object and breaks the cycle.
+                    zero_or_more(BoxedParser::new(|input| element().parse(input))),
+                    close_element(el.name.clone()),
+                )
+                .map(move |children| {
+                    let mut el = el.clone();
+                    el.children = children;
+                    el
+                })
+            })
+            .describe(shape),
+    )
+    .name("parent_element")
 }
 
 pub fn whitespace_wrap<'a, P, A>(parser: P) -> impl Parser<'a, A> 
@@ -148,7 +206,7 @@ where
 }
 
 pub fn element<'a>() -> impl Parser<'a, Element> {
-    either(single_element(), parent_element())
+    either(single_element(), parent_element()).name("element")
 }
 
 #[cfg(test)]
@@ -160,7 +218,7 @@ mod tests {
         let parse_foo = match_literal("foo");
         assert_eq!(Ok(((), "")), parse_foo.parse("foo"));
         assert_eq!(Ok(((), " bar")), parse_foo.parse("foo bar"));
-        assert_eq!(Err("baz"), parse_foo.parse("baz"));
+        assert_eq!(Err(ParseError::new("baz", "foo")), parse_foo.parse("baz"));
     }
 
     #[test]
@@ -170,8 +228,11 @@ mod tests {
             Ok((((), "first-element".to_owned()), "/>")),
             open_tag.parse("<first-element/>"),
         );
-        assert_eq!(Err("oops"), open_tag.parse("oops"));
-        assert_eq!(Err("!oops"), open_tag.parse("<!oops"));
+        assert_eq!(Err(ParseError::new("oops", "<")), open_tag.parse("oops"));
+        assert_eq!(
+            Err(ParseError::new("!oops", "identifier").shift(1)),
+            open_tag.parse("<!oops")
+        );
     }
 
     #[test]
@@ -182,8 +243,11 @@ mod tests {
             Ok(("first-element".to_owned(), "/>")),
             open_tag.parse("<first-element/>")
         );
-        assert_eq!(Err("oops"), open_tag.parse("oops"));
-        assert_eq!(Err("!oops"), open_tag.parse("<!oops"));
+        assert_eq!(Err(ParseError::new("oops", "<")), open_tag.parse("oops"));
+        assert_eq!(
+            Err(ParseError::new("!oops", "identifier").shift(1)),
+            open_tag.parse("<!oops")
+        );
     }
 
     #[test]
@@ -200,8 +264,14 @@ mod tests {
             Ok(("Hello World!".to_owned(), "")),
             parser.parse("\"Hello World!\"")
         );
-        assert_eq!(Err(""), parser.parse("\"Hello World!"));
-        assert_eq!(Err("Hello World!\""), parser.parse("Hello World!\""));
+        assert_eq!(
+            Err(ParseError::new("", "\"").shift(13)),
+            parser.parse("\"Hello World!")
+        );
+        assert_eq!(
+            Err(ParseError::new("Hello World!\"", "\"")),
+            parser.parse("Hello World!\"")
+        );
     }
 
     #[test]
@@ -286,6 +356,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn element_grammar() {
+        let grammar = element().grammar();
+
+        assert_eq!(grammar.root, Representation::Nonterminal("element".to_string()));
+        assert_eq!(grammar.to_string().lines().next().unwrap(), "element ;");
+        assert!(grammar
+            .productions
+            .iter()
+            .any(|(name, expansion)| name == "element"
+                && expansion.to_string() == "single_element | parent_element"));
+
+        // `parent_element` recurses back through `element()`; naming it
+        // is what stops that from expanding forever. Its own expansion
+        // should still mention both halves of `open_element().and_then(...)`
+        // — the open tag *and* the recursive children / closing tag that
+        // `AndThen`'s default representation can't see on its own (see
+        // `parent_element`'s `.describe(...)`), not just the first half.
+        let parent_element_expansion = grammar
+            .productions
+            .iter()
+            .find(|(name, _)| name == "parent_element")
+            .map(|(_, expansion)| expansion.to_string())
+            .expect("parent_element should have a production");
+        assert!(parent_element_expansion.contains("{ element }"), "{}", parent_element_expansion);
+        assert!(parent_element_expansion.contains(r#""</""#), "{}", parent_element_expansion);
+
+        // `identifier` is reachable from both `single_element` (via
+        // `self_closing_element`) and `parent_element` (via
+        // `open_element`); it must only be printed once.
+        let identifier_productions = grammar
+            .productions
+            .iter()
+            .filter(|(name, _)| name == "identifier")
+            .count();
+        assert_eq!(identifier_productions, 1);
+    }
+
+    #[test]
+    fn malformed_attribute_is_a_fatal_error() {
+        // Once `<div` has matched, a malformed attribute list must not
+        // silently backtrack into `parent_element()` and report a vague
+        // "not an element" failure: it should be a fatal error pinpointing
+        // the bad attribute.
+        let err = element().parse(r#"<div oops></div>"#).unwrap_err();
+        assert!(err.fatal);
+        assert_eq!(err.expected, vec!["="]);
+        // The offset must point at the actual failure ("oops" has no `=`),
+        // not somewhere earlier in the input.
+        assert_eq!(err.offset, 9);
+    }
+
+    #[test]
+    fn missing_closing_angle_bracket_is_a_fatal_error() {
+        let err = element().parse(r#"<div a="1" 5></div>"#).unwrap_err();
+        assert!(err.fatal);
+        assert_eq!(err.expected, vec![">"]);
+        assert_eq!(err.offset, 11);
+    }
+
     #[test]
     fn xml_parser() {
         let doc = r#"
@@ -0,0 +1,45 @@
+use parcom::json::{json_value, JsonValue};
+use parcom::parser::Parser;
+use proptest::prelude::*;
+
+fn arb_json_value() -> impl Strategy<Value = JsonValue> {
+    let leaf = prop_oneof![
+        Just(JsonValue::Null),
+        any::<bool>().prop_map(JsonValue::Bool),
+        any::<f64>()
+            .prop_filter("finite", |n| n.is_finite())
+            .prop_map(JsonValue::Number),
+        "[a-zA-Z0-9 ]{0,16}".prop_map(JsonValue::String),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(JsonValue::Array),
+            prop::collection::vec(
+                ("[a-zA-Z][a-zA-Z0-9]{0,8}".prop_map(String::from), inner),
+                0..8,
+            )
+            .prop_map(JsonValue::Object),
+        ]
+    })
+}
+
+proptest! {
+    /// Any `JsonValue` we can generate should survive a serialize/parse
+    /// round trip unchanged.
+    #[test]
+    fn round_trips(value in arb_json_value()) {
+        let text = value.to_string();
+        let (parsed, rest) = json_value().parse(&text).unwrap();
+        prop_assert_eq!(rest, "");
+        prop_assert_eq!(parsed, value);
+    }
+
+    /// The parser should reject or accept arbitrary input, but never panic
+    /// (no stack overflow, no out-of-bounds slicing) no matter what garbage
+    /// it's fed.
+    #[test]
+    fn never_panics_on_arbitrary_input(input in ".*") {
+        let _ = json_value().parse(&input);
+    }
+}